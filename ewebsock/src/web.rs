@@ -1,3 +1,6 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
 use crate::{EventHandler, Result, WsEvent, WsMessage};
 
 macro_rules! console_log {
@@ -12,19 +15,26 @@ fn string_from_js_string(s: js_sys::JsString) -> String {
     s.as_string().unwrap_or(format!("{:#?}", s))
 }
 
+/// The initial delay before the first reconnect attempt.
+const RECONNECT_INITIAL_DELAY_MS: i32 = 250;
+/// Reconnect delays double after every failed attempt, up to this cap.
+const RECONNECT_MAX_DELAY_MS: i32 = 30_000;
+
 #[derive(Clone)]
 pub struct WsSender {
-    ws: web_sys::WebSocket,
+    state: Rc<RefCell<Option<ConnectionState>>>,
 }
 
 impl WsSender {
     pub fn send(&mut self, msg: WsMessage) {
+        let borrowed = self.state.borrow();
+        let state = borrowed.as_ref().expect("connection state is always set");
         let result = match msg {
             WsMessage::Binary(data) => {
-                self.ws.set_binary_type(web_sys::BinaryType::Blob);
-                self.ws.send_with_u8_array(&data)
+                state.ws.set_binary_type(web_sys::BinaryType::Blob);
+                state.ws.send_with_u8_array(&data)
             }
-            WsMessage::Text(text) => self.ws.send_with_str(&text),
+            WsMessage::Text(text) => state.ws.send_with_str(&text),
             unknown => {
                 panic!("Don't know how to send message: {:?}", unknown);
             }
@@ -35,21 +45,62 @@ impl WsSender {
     }
 }
 
+/// Everything kept alive for as long as we want the socket connected: the socket itself, its
+/// event-handler closures (so they aren't deallocated out from under the JS side), and the
+/// currently-scheduled reconnect timer (if any). Replacing a field here drops its old value,
+/// unlike `Closure::forget`, so reconnecting repeatedly doesn't leak.
+struct ConnectionState {
+    ws: web_sys::WebSocket,
+    _onmessage: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+    _onerror: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::ErrorEvent)>,
+    _onopen: wasm_bindgen::closure::Closure<dyn FnMut(wasm_bindgen::JsValue)>,
+    _onclose: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::CloseEvent)>,
+    pending_reconnect: Option<wasm_bindgen::closure::Closure<dyn FnMut()>>,
+}
+
 pub fn ws_connect(url: String, on_event: EventHandler) -> Result<WsSender> {
     // Based on https://rustwasm.github.io/wasm-bindgen/examples/websockets.html
 
-    console_log!("spawn_ws_client");
-    use wasm_bindgen::closure::Closure;
-    use wasm_bindgen::JsCast as _;
+    let url = Rc::new(url);
+    let reconnect_delay_ms = Rc::new(Cell::new(RECONNECT_INITIAL_DELAY_MS));
+
+    // `state` is created empty first so `connect` can be handed a weak reference to it: the
+    // `onclose` callback it wires up needs to reach back in on reconnect without
+    // holding a strong `Rc` (which would keep the whole thing alive forever via a cycle).
+    let state: Rc<RefCell<Option<ConnectionState>>> = Rc::new(RefCell::new(None));
+    let weak_state = Rc::downgrade(&state);
+    let initial_state = connect(&url, on_event, reconnect_delay_ms, weak_state)?;
+    *state.borrow_mut() = Some(initial_state);
 
-    // Connect to an server
-    let ws = web_sys::WebSocket::new(&url).map_err(string_from_js_value)?;
+    Ok(WsSender { state })
+}
+
+fn new_websocket(url: &str) -> Result<web_sys::WebSocket> {
+    console_log!("spawn_ws_client");
+    let ws = web_sys::WebSocket::new(url).map_err(string_from_js_value)?;
 
     // For small binary messages, like CBOR, Arraybuffer is more efficient than Blob handling
     ws.set_binary_type(web_sys::BinaryType::Arraybuffer);
+    Ok(ws)
+}
+
+/// Open a socket and wire up its callbacks. `weak_state` is a weak reference to the
+/// `Rc<RefCell<ConnectionState>>` that will hold the result, so the `onclose` callback can
+/// reach back in to replace it on reconnect without creating an `Rc` cycle. `onerror` only
+/// reports the error; the reconnect is scheduled by the `close` event that follows it.
+fn connect(
+    url: &Rc<String>,
+    on_event: EventHandler,
+    reconnect_delay_ms: Rc<Cell<i32>>,
+    weak_state: Weak<RefCell<Option<ConnectionState>>>,
+) -> Result<ConnectionState> {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast as _;
+
+    let ws = new_websocket(url)?;
 
     // onmessage callback
-    {
+    let onmessage = {
         let on_event = on_event.clone();
         let onmessage_callback = Closure::wrap(Box::new(move |e: web_sys::MessageEvent| {
             // Handle difference Text/Binary,...
@@ -75,6 +126,8 @@ pub fn ws_connect(url: String, on_event: EventHandler) -> Result<WsSender> {
                     as Box<dyn FnMut(web_sys::ProgressEvent)>);
                 fr.set_onloadend(Some(onloadend_cb.as_ref().unchecked_ref()));
                 fr.read_as_array_buffer(&blob).expect("blob not readable");
+                // This one really is fire-once-per-message, not tied to the connection's
+                // lifetime, so there's nothing long-lived to store it in.
                 onloadend_cb.forget();
             } else if let Ok(txt) = e.data().dyn_into::<js_sys::JsString>() {
                 console_log!("message event, received Text: {:?}", txt);
@@ -89,31 +142,126 @@ pub fn ws_connect(url: String, on_event: EventHandler) -> Result<WsSender> {
             }
         }) as Box<dyn FnMut(web_sys::MessageEvent)>);
 
-        // set message event handler on WebSocket
         ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
+        onmessage_callback
+    };
 
-        // forget the callback to keep it alive
-        onmessage_callback.forget();
-    }
-
-    {
+    let onerror = {
         let on_event = on_event.clone();
         let onerror_callback = Closure::wrap(Box::new(move |error_event: web_sys::ErrorEvent| {
             console_log!("error event: {:?}", error_event);
             on_event(WsEvent::Error(error_event.message()));
         }) as Box<dyn FnMut(web_sys::ErrorEvent)>);
         ws.set_onerror(Some(onerror_callback.as_ref().unchecked_ref()));
-        onerror_callback.forget();
-    }
+        onerror_callback
+    };
 
-    {
+    let onopen = {
+        let on_event = on_event.clone();
+        let reconnect_delay_ms = reconnect_delay_ms.clone();
         let onopen_callback = Closure::wrap(Box::new(move |_| {
             console_log!("socket opened");
+            // A successful connection means the flakiness is over (for now): reset the backoff.
+            reconnect_delay_ms.set(RECONNECT_INITIAL_DELAY_MS);
             on_event(WsEvent::Opened);
         }) as Box<dyn FnMut(wasm_bindgen::JsValue)>);
         ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
-        onopen_callback.forget();
-    }
+        onopen_callback
+    };
+
+    let onclose = {
+        let url = url.clone();
+        let on_event = on_event.clone();
+        let reconnect_delay_ms = reconnect_delay_ms.clone();
+        let weak_state = weak_state.clone();
+        let onclose_callback = Closure::wrap(Box::new(move |close_event: web_sys::CloseEvent| {
+            console_log!("close event: {:?}", close_event);
+            on_event(WsEvent::Closed {
+                code: close_event.code(),
+                reason: close_event.reason(),
+                was_clean: close_event.was_clean(),
+            });
+            schedule_reconnect(
+                url.clone(),
+                on_event.clone(),
+                reconnect_delay_ms.clone(),
+                weak_state.clone(),
+            );
+        }) as Box<dyn FnMut(web_sys::CloseEvent)>);
+        ws.set_onclose(Some(onclose_callback.as_ref().unchecked_ref()));
+        onclose_callback
+    };
+
+    Ok(ConnectionState {
+        ws,
+        _onmessage: onmessage,
+        _onerror: onerror,
+        _onopen: onopen,
+        _onclose: onclose,
+        pending_reconnect: None,
+    })
+}
+
+/// Schedule a reconnect attempt after the current backoff delay, doubling it (up to the cap)
+/// for next time. The timer closure is stashed in `state.pending_reconnect` so it lives until
+/// it fires, rather than being leaked with `Closure::forget` on every single reconnect.
+fn schedule_reconnect(
+    url: Rc<String>,
+    on_event: EventHandler,
+    reconnect_delay_ms: Rc<Cell<i32>>,
+    weak_state: Weak<RefCell<Option<ConnectionState>>>,
+) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast as _;
 
-    Ok(WsSender { ws })
+    let Some(state) = weak_state.upgrade() else {
+        // The `WsSender` (and everyone else) has been dropped; nothing left to reconnect for.
+        return;
+    };
+
+    let delay_ms = reconnect_delay_ms.get();
+    reconnect_delay_ms.set((delay_ms * 2).min(RECONNECT_MAX_DELAY_MS));
+
+    let reconnect_callback = Closure::once(Box::new(move || {
+        console_log!("attempting to reconnect to {:?}", url);
+        let Some(state) = weak_state.upgrade() else {
+            return;
+        };
+        match connect(
+            &url,
+            on_event.clone(),
+            reconnect_delay_ms.clone(),
+            weak_state.clone(),
+        ) {
+            Ok(new_state) => {
+                // This callback is itself owned by `state.pending_reconnect`. Overwriting
+                // `state` would drop that `Closure` in the middle of the assignment, so take it
+                // into a local first; the local is dropped when this arm ends.
+                let _still_running_closure =
+                    state.borrow_mut().as_mut().and_then(|old| old.pending_reconnect.take());
+                *state.borrow_mut() = Some(new_state);
+            }
+            Err(err) => {
+                tracing::error!("Failed to reconnect: {:?}", err);
+                schedule_reconnect(url, on_event, reconnect_delay_ms, weak_state);
+            }
+        }
+    }) as Box<dyn FnOnce()>);
+
+    if let Some(window) = web_sys::window() {
+        if let Err(err) = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            reconnect_callback.as_ref().unchecked_ref(),
+            delay_ms,
+        ) {
+            tracing::error!(
+                "Failed to schedule reconnect: {:?}",
+                string_from_js_value(err)
+            );
+        }
+    }
+    // `state` is always `Some` by the time anyone outside `connect`/`schedule_reconnect` can
+    // observe it; stash the timer here rather than in a local so it survives until it fires.
+    if let Some(state) = state.borrow_mut().as_mut() {
+        state.pending_reconnect = Some(reconnect_callback);
+    }
 }
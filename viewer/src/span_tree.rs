@@ -11,6 +11,9 @@ pub struct SpanTree {
     nodes: HashMap<SpanId, SpanNode>,
     roots: HashSet<SpanId>,
     orphan_events: Vec<(Time, rr_data::DataEvent)>,
+    /// Bumped on every [`Self::on_mesage`] call, so UI-side caches (like the filter match set
+    /// in [`Self::tree_ui`]) can tell stale data apart from an unchanged tree.
+    generation: u64,
 }
 
 /// A span is created, and then is opened over many non-overlapping intervals.
@@ -50,6 +53,8 @@ impl std::fmt::Display for TimeInterval {
 
 impl SpanTree {
     pub fn on_mesage(&mut self, message: &rr_data::Message) {
+        self.generation += 1;
+
         let rr_data::Message { log_time, msg_enum } = message;
         match &msg_enum {
             rr_data::MessageEnum::NewCallsite(callsite) => {
@@ -181,23 +186,789 @@ impl SpanTree {
         use itertools::Itertools as _;
         ancestry.iter().rev().join(" ➡ ")
     }
+
+    /// Walk up the parent chain to find the root span of `span_id` (which may be itself).
+    fn root_span_id(&self, span_id: &SpanId) -> SpanId {
+        let mut current = *span_id;
+        while let Some(parent) = self
+            .nodes
+            .get(&current)
+            .and_then(|node| node.span.parent_span_id)
+        {
+            current = parent;
+        }
+        current
+    }
+
+    /// A stable, Chrome-trace-friendly thread id derived from a span's root span.
+    /// Spans sharing a root (and therefore a tree) get the same `tid`, so siblings stack correctly.
+    fn chrome_trace_tid(&self, span_id: &SpanId) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+        let root = self.root_span_id(span_id);
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        root.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// ## Export
+impl SpanTree {
+    /// Serialize everything we know into the [Chrome Trace Event Format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU/preview),
+    /// so the recording can be opened in `chrome://tracing` or <https://ui.perfetto.dev> for zoomable timeline analysis.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut events = Vec::new();
+
+        for (span_id, node) in &self.nodes {
+            let name = self.span_name(span_id);
+            let cat = self
+                .callsites
+                .get(&node.span.callsite_id)
+                .map_or_else(String::new, |callsite| callsite.level.to_string());
+            let tid = self.chrome_trace_tid(span_id);
+
+            for interval in &node.intervals {
+                match (interval.entered, interval.exited) {
+                    (Some(entered), Some(exited)) => {
+                        events.push(format!(
+                            r#"{{"ph":"X","name":{name},"cat":{cat},"ts":{ts},"dur":{dur},"pid":1,"tid":{tid}}}"#,
+                            name = json_string(&name),
+                            cat = json_string(&cat),
+                            ts = time_to_micros(entered),
+                            dur = time_to_micros(exited) - time_to_micros(entered),
+                        ));
+                    }
+                    (Some(entered), None) => {
+                        // Still open: show it as a begin event so the viewer renders it as ongoing.
+                        events.push(format!(
+                            r#"{{"ph":"B","name":{name},"cat":{cat},"ts":{ts},"pid":1,"tid":{tid}}}"#,
+                            name = json_string(&name),
+                            cat = json_string(&cat),
+                            ts = time_to_micros(entered),
+                        ));
+                    }
+                    (None, _) => {}
+                }
+            }
+
+            for (time, data_event) in &node.events {
+                events.push(chrome_trace_instant_event(
+                    &self.event_name(data_event),
+                    *time,
+                    tid,
+                    data_event,
+                ));
+            }
+        }
+
+        for (time, data_event) in &self.orphan_events {
+            events.push(chrome_trace_instant_event(
+                &self.event_name(data_event),
+                *time,
+                0,
+                data_event,
+            ));
+        }
+
+        format!(
+            r#"{{"traceEvents":[{events}],"displayTimeUnit":"ms"}}"#,
+            events = events.join(",")
+        )
+    }
+
+    fn event_name(&self, data_event: &rr_data::DataEvent) -> String {
+        self.callsites
+            .get(&data_event.callsite_id)
+            .map_or_else(|| "event".to_owned(), |callsite| callsite.name.to_string())
+    }
+
+    /// Render the recorded span structure as a Graphviz `digraph`: one node per span, solid
+    /// parent→child edges from `children`, dashed edges for `SpanFollowsFrom` relationships.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph spans {\n");
+        dot.push_str("    rankdir=TB;\n");
+
+        for (span_id, node) in &self.nodes {
+            let name = self.span_name(span_id);
+            let callsite = self.callsites.get(&node.span.callsite_id);
+            let level = callsite.map_or_else(String::new, |c| c.level.to_string());
+            let total_micros: f64 = node
+                .intervals
+                .iter()
+                .filter_map(closed_interval_micros)
+                .map(|(entered, exited)| exited - entered)
+                .sum();
+            let shape = if self.roots.contains(span_id) {
+                "box"
+            } else {
+                "ellipse"
+            };
+            // Escape only the untrusted `name` before splicing it into the label template: the
+            // `\n` below is a literal Graphviz line-break escape, not a character to re-escape.
+            let label = format!(
+                "{name}\\n{count} interval(s), {total_micros:.1}µs",
+                name = dot_escape(&name),
+                count = node.intervals.len(),
+            );
+
+            dot.push_str(&format!(
+                "    \"{id}\" [label=\"{label}\", shape={shape}, color=\"{color}\"];\n",
+                id = dot_escape(&span_id.to_string()),
+                color = dot_level_color(&level),
+            ));
+        }
+
+        for (span_id, node) in &self.nodes {
+            for child_id in &node.children {
+                dot.push_str(&format!(
+                    "    \"{parent}\" -> \"{child}\";\n",
+                    parent = dot_escape(&span_id.to_string()),
+                    child = dot_escape(&child_id.to_string()),
+                ));
+            }
+            if let Some(follows) = node.follows {
+                dot.push_str(&format!(
+                    "    \"{follows}\" -> \"{span}\" [style=dashed];\n",
+                    follows = dot_escape(&follows.to_string()),
+                    span = dot_escape(&span_id.to_string()),
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn dot_level_color(level: &str) -> &'static str {
+    match level.to_lowercase().as_str() {
+        "error" => "red",
+        "warn" | "warning" => "orange",
+        "info" => "darkgreen",
+        "debug" => "blue",
+        "trace" => "gray",
+        _ => "black",
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod dot_escape_tests {
+    use super::*;
+
+    #[test]
+    fn escapes_backslashes_and_quotes() {
+        assert_eq!(dot_escape(r#"a\b"c"#), r#"a\\b\"c"#);
+    }
+
+    #[test]
+    fn leaves_plain_text_untouched() {
+        assert_eq!(dot_escape("my_span"), "my_span");
+    }
+}
+
+fn chrome_trace_instant_event(
+    name: &str,
+    time: Time,
+    tid: u64,
+    data_event: &rr_data::DataEvent,
+) -> String {
+    let args = data_event
+        .fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(&value.to_string())))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{"ph":"i","s":"g","name":{name},"ts":{ts},"pid":1,"tid":{tid},"args":{{{args}}}}}"#,
+        name = json_string(name),
+        ts = time_to_micros(time),
+    )
+}
+
+fn time_to_micros(time: Time) -> f64 {
+    time.as_micros_f64()
+}
+
+fn micros_to_time(micros: f64) -> Time {
+    Time::from_micros_f64(micros)
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Offer `contents` as a file download, e.g. from a button click.
+#[cfg(target_arch = "wasm32")]
+fn download_text(filename: &str, mime: &str, contents: &str) {
+    use wasm_bindgen::{JsCast as _, JsValue};
+
+    let parts = js_sys::Array::new();
+    parts.push(&JsValue::from_str(contents));
+    let blob_parts = web_sys::BlobPropertyBag::new();
+    blob_parts.set_type(mime);
+    if let Ok(blob) = web_sys::Blob::new_with_str_sequence_and_options(&parts, &blob_parts) {
+        if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
+            if let Some(window) = web_sys::window() {
+                if let Some(document) = window.document() {
+                    if let Ok(anchor) = document.create_element("a") {
+                        let anchor: web_sys::HtmlAnchorElement = anchor.unchecked_into();
+                        anchor.set_href(&url);
+                        anchor.set_download(filename);
+                        anchor.click();
+                    }
+                }
+            }
+            let _ = web_sys::Url::revoke_object_url(&url);
+        }
+    }
+}
+
+/// Offer `contents` as a file download, e.g. from a button click.
+#[cfg(not(target_arch = "wasm32"))]
+fn download_text(filename: &str, _mime: &str, contents: &str) {
+    if let Err(err) = std::fs::write(filename, contents) {
+        tracing::error!("Failed to write {filename:?}: {err}");
+    }
+}
+
+/// Aggregated timing for all spans sharing a [`rr_data::CallsiteId`].
+#[derive(Debug, Clone, Copy)]
+pub struct CallsiteStats {
+    pub call_count: usize,
+    pub total_micros: f64,
+    pub self_micros: f64,
+    pub min_micros: f64,
+    pub max_micros: f64,
+}
+
+impl Default for CallsiteStats {
+    fn default() -> Self {
+        Self {
+            call_count: 0,
+            total_micros: 0.0,
+            self_micros: 0.0,
+            // `0.0` is a legitimate duration, so it can't double as the "unset" sentinel.
+            min_micros: f64::INFINITY,
+            max_micros: 0.0,
+        }
+    }
+}
+
+impl CallsiteStats {
+    pub fn mean_micros(&self) -> f64 {
+        if self.call_count == 0 {
+            0.0
+        } else {
+            self.total_micros / self.call_count as f64
+        }
+    }
+}
+
+/// ## Profiler
+impl SpanTree {
+    /// Recompute per-callsite aggregated timing from the current `nodes`.
+    pub fn compute_callsite_stats(&self) -> HashMap<rr_data::CallsiteId, CallsiteStats> {
+        let mut stats: HashMap<rr_data::CallsiteId, CallsiteStats> = HashMap::new();
+
+        for node in self.nodes.values() {
+            let entry = stats.entry(node.span.callsite_id).or_default();
+
+            // One `NewSpan` is one call, regardless of how many times it was entered/exited
+            // (an async span suspended and resumed across `.await` points re-enters its
+            // interval many times without being a new call).
+            entry.call_count += 1;
+
+            let children_intervals = merge_micros_intervals(
+                node.children
+                    .iter()
+                    .filter_map(|child_id| self.nodes.get(child_id))
+                    .flat_map(|child| &child.intervals)
+                    .filter_map(closed_interval_micros)
+                    .collect(),
+            );
+
+            for interval in &node.intervals {
+                let Some((entered, exited)) = closed_interval_micros(interval) else {
+                    continue;
+                };
+                let duration = exited - entered;
+
+                entry.total_micros += duration;
+                entry.self_micros += subtract_covered_micros((entered, exited), &children_intervals);
+                entry.min_micros = entry.min_micros.min(duration);
+                entry.max_micros = entry.max_micros.max(duration);
+            }
+        }
+
+        stats
+    }
+
+    pub fn profiler_ui(&self, ui: &mut egui::Ui) {
+        let mut stats: Vec<_> = self.compute_callsite_stats().into_iter().collect();
+
+        let sort_id = egui::Id::new("profiler_sort");
+        let mut sort = ui.data_mut(|data| {
+            *data.get_temp_mut_or_insert_with(sort_id, || (ProfilerColumn::Total, true))
+        });
+
+        let sort_column_clicked = |ui: &mut egui::Ui, label: &str, column: ProfilerColumn| {
+            let clicked = ui.button(label).clicked();
+            if clicked {
+                if sort.0 == column {
+                    sort.1 = !sort.1;
+                } else {
+                    sort = (column, true);
+                }
+            }
+            clicked
+        };
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                egui::Grid::new("callsite_stats")
+                    .num_columns(5)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        sort_column_clicked(ui, "Name", ProfilerColumn::Name);
+                        sort_column_clicked(ui, "Count", ProfilerColumn::Count);
+                        sort_column_clicked(ui, "Total (µs)", ProfilerColumn::Total);
+                        sort_column_clicked(ui, "Self (µs)", ProfilerColumn::SelfTime);
+                        sort_column_clicked(ui, "Mean (µs)", ProfilerColumn::Mean);
+                        ui.end_row();
+
+                        stats.sort_by(|(a_id, a), (b_id, b)| {
+                            let ordering = match sort.0 {
+                                ProfilerColumn::Name => {
+                                    self.callsite_name(a_id).cmp(&self.callsite_name(b_id))
+                                }
+                                ProfilerColumn::Count => a.call_count.cmp(&b.call_count),
+                                ProfilerColumn::Total => {
+                                    a.total_micros.partial_cmp(&b.total_micros).unwrap()
+                                }
+                                ProfilerColumn::SelfTime => {
+                                    a.self_micros.partial_cmp(&b.self_micros).unwrap()
+                                }
+                                ProfilerColumn::Mean => {
+                                    a.mean_micros().partial_cmp(&b.mean_micros()).unwrap()
+                                }
+                            };
+                            if sort.1 {
+                                ordering.reverse()
+                            } else {
+                                ordering
+                            }
+                        });
+
+                        for (callsite_id, stat) in &stats {
+                            ui.label(self.callsite_name(callsite_id));
+                            ui.label(stat.call_count.to_string());
+                            ui.label(format!("{:.1}", stat.total_micros));
+                            ui.label(format!("{:.1}", stat.self_micros));
+                            ui.label(format!("{:.1}", stat.mean_micros()));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.data_mut(|data| data.insert_temp(sort_id, sort));
+    }
+
+    fn callsite_name(&self, callsite_id: &rr_data::CallsiteId) -> String {
+        self.callsites
+            .get(callsite_id)
+            .map_or_else(|| callsite_id.to_string(), |callsite| callsite.name.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProfilerColumn {
+    Name,
+    Count,
+    Total,
+    SelfTime,
+    Mean,
+}
+
+fn closed_interval_micros(interval: &TimeInterval) -> Option<(f64, f64)> {
+    Some((
+        time_to_micros(interval.entered?),
+        time_to_micros(interval.exited?),
+    ))
+}
+
+/// Merge a set of (possibly overlapping) `(start, end)` ranges into a sorted, non-overlapping set.
+fn merge_micros_intervals(mut intervals: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    intervals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in intervals {
+        if let Some(last) = merged.last_mut() {
+            if start <= last.1 {
+                last.1 = last.1.max(end);
+                continue;
+            }
+        }
+        merged.push((start, end));
+    }
+    merged
+}
+
+/// How much of `interval` is *not* covered by any range in the (sorted, non-overlapping) `covering` set.
+fn subtract_covered_micros(interval: (f64, f64), covering: &[(f64, f64)]) -> f64 {
+    let (mut start, end) = interval;
+    let mut remaining = 0.0;
+    for &(covered_start, covered_end) in covering {
+        if covered_end <= start || covered_start >= end {
+            continue;
+        }
+        if covered_start > start {
+            remaining += covered_start - start;
+        }
+        start = start.max(covered_end);
+        if start >= end {
+            break;
+        }
+    }
+    if start < end {
+        remaining += end - start;
+    }
+    remaining
+}
+
+#[cfg(test)]
+mod callsite_stats_tests {
+    use super::*;
+
+    #[test]
+    fn merge_micros_intervals_merges_overlapping_and_adjacent_ranges() {
+        let merged =
+            merge_micros_intervals(vec![(0.0, 10.0), (5.0, 15.0), (20.0, 30.0), (30.0, 40.0)]);
+        assert_eq!(merged, vec![(0.0, 15.0), (20.0, 40.0)]);
+    }
+
+    #[test]
+    fn subtract_covered_micros_removes_fully_covered_interval() {
+        assert_eq!(subtract_covered_micros((0.0, 10.0), &[(0.0, 10.0)]), 0.0);
+    }
+
+    #[test]
+    fn subtract_covered_micros_leaves_uncovered_gaps() {
+        // [0, 2) and [5, 10) are uncovered; [2, 5) is covered by the child.
+        assert_eq!(subtract_covered_micros((0.0, 10.0), &[(2.0, 5.0)]), 7.0);
+    }
+
+    #[test]
+    fn subtract_covered_micros_ignores_non_overlapping_ranges() {
+        assert_eq!(subtract_covered_micros((0.0, 10.0), &[(20.0, 30.0)]), 10.0);
+    }
+
+    #[test]
+    fn min_micros_survives_a_zero_duration_interval() {
+        // Regression test: `0.0` used to double as the "unset" sentinel, so a later
+        // zero-duration interval would make the running minimum forget smaller values.
+        let mut stats = CallsiteStats::default();
+        for &duration in &[0.0, 50.0, 3.0, 10.0] {
+            stats.min_micros = stats.min_micros.min(duration);
+        }
+        assert_eq!(stats.min_micros, 0.0);
+    }
+}
+
+/// A query that prunes which spans/events are shown in [`SpanTree::tree_ui`].
+///
+/// An empty filter matches everything. Otherwise a span passes if it (or any descendant)
+/// matches, so ancestors stay visible as context even if they don't match themselves.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanFilter {
+    /// Only show spans whose callsite level is in this set, if set.
+    pub levels: Option<Vec<rr_data::Level>>,
+    /// Case-insensitive substring match against the callsite name or location.
+    pub name_query: String,
+    /// Only show spans from these callsites, if set.
+    pub callsite_ids: Option<HashSet<rr_data::CallsiteId>>,
+    /// Only show spans with at least one interval overlapping this `[from, to]` window, if set.
+    pub time_window: Option<(Time, Time)>,
+    /// Case-insensitive substring match against a `DataEvent` field key.
+    pub field_key: String,
+    /// Case-insensitive substring match against a `DataEvent` field value.
+    pub field_value: String,
+}
+
+/// Whether a `[entered, exited]` interval (either end possibly unknown, meaning "unbounded in
+/// that direction") overlaps the closed window `[window.0, window.1]`.
+fn interval_overlaps_window<T: PartialOrd + Copy>(
+    entered: Option<T>,
+    exited: Option<T>,
+    window: (T, T),
+) -> bool {
+    let (from, to) = window;
+    entered.map_or(true, |entered| entered <= to) && exited.map_or(true, |exited| exited >= from)
+}
+
+impl SpanFilter {
+    pub fn is_active(&self) -> bool {
+        self.levels.is_some()
+            || !self.name_query.is_empty()
+            || self.callsite_ids.is_some()
+            || self.time_window.is_some()
+            || !self.field_key.is_empty()
+            || !self.field_value.is_empty()
+    }
+}
+
+/// ## Filtering
+impl SpanTree {
+    /// The set of spans that match `filter`, either directly or because a descendant does.
+    pub fn matching_spans(&self, filter: &SpanFilter) -> HashSet<SpanId> {
+        let mut memo = HashMap::new();
+        for span_id in self.nodes.keys().copied().collect::<Vec<_>>() {
+            self.span_matches(&span_id, filter, &mut memo);
+        }
+        memo.into_iter()
+            .filter_map(|(span_id, matches)| matches.then_some(span_id))
+            .collect()
+    }
+
+    fn span_matches(
+        &self,
+        span_id: &SpanId,
+        filter: &SpanFilter,
+        memo: &mut HashMap<SpanId, bool>,
+    ) -> bool {
+        if let Some(&cached) = memo.get(span_id) {
+            return cached;
+        }
+        // Guard against cycles before we know the answer, so a malformed parent/child loop
+        // can't recurse forever.
+        memo.insert(*span_id, false);
+
+        let Some(node) = self.nodes.get(span_id) else {
+            return false;
+        };
+
+        let result = self.span_matches_self(span_id, node, filter)
+            || node
+                .children
+                .iter()
+                .any(|child_id| self.span_matches(child_id, filter, memo));
+
+        memo.insert(*span_id, result);
+        result
+    }
+
+    fn span_matches_self(&self, span_id: &SpanId, node: &SpanNode, filter: &SpanFilter) -> bool {
+        let callsite = self.callsites.get(&node.span.callsite_id);
+
+        if let Some(levels) = &filter.levels {
+            if !callsite.is_some_and(|c| levels.contains(&c.level)) {
+                return false;
+            }
+        }
+
+        if let Some(callsite_ids) = &filter.callsite_ids {
+            if !callsite_ids.contains(&node.span.callsite_id) {
+                return false;
+            }
+        }
+
+        if !filter.name_query.is_empty() {
+            let query = filter.name_query.to_lowercase();
+            let name_matches = self.span_name(span_id).to_lowercase().contains(&query);
+            let location_matches = callsite
+                .is_some_and(|c| c.location.to_string().to_lowercase().contains(&query));
+            if !name_matches && !location_matches {
+                return false;
+            }
+        }
+
+        if let Some(window) = filter.time_window {
+            let overlaps_window = node
+                .intervals
+                .iter()
+                .any(|interval| interval_overlaps_window(interval.entered, interval.exited, window));
+            if !overlaps_window {
+                return false;
+            }
+        }
+
+        if !filter.field_key.is_empty() || !filter.field_value.is_empty() {
+            if !node
+                .events
+                .iter()
+                .any(|(_, event)| self.event_matches_fields(event, filter))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn event_matches_fields(&self, data_event: &rr_data::DataEvent, filter: &SpanFilter) -> bool {
+        data_event.fields.iter().any(|(key, value)| {
+            (filter.field_key.is_empty()
+                || key.to_lowercase().contains(&filter.field_key.to_lowercase()))
+                && (filter.field_value.is_empty()
+                    || value
+                        .to_string()
+                        .to_lowercase()
+                        .contains(&filter.field_value.to_lowercase()))
+        })
+    }
+
+    /// Whether an orphan (span-less) event passes `filter`.
+    fn orphan_event_matches(&self, data_event: &rr_data::DataEvent, filter: &SpanFilter) -> bool {
+        if let Some(levels) = &filter.levels {
+            if !self
+                .callsites
+                .get(&data_event.callsite_id)
+                .is_some_and(|c| levels.contains(&c.level))
+            {
+                return false;
+            }
+        }
+        if let Some(callsite_ids) = &filter.callsite_ids {
+            if !callsite_ids.contains(&data_event.callsite_id) {
+                return false;
+            }
+        }
+        if !filter.field_key.is_empty() || !filter.field_value.is_empty() {
+            if !self.event_matches_fields(data_event, filter) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod span_filter_tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_interval_matches() {
+        assert!(interval_overlaps_window(Some(12), Some(18), (10, 20)));
+    }
+
+    #[test]
+    fn interval_spanning_the_whole_window_matches() {
+        // Regression test: an interval that fully contains `[from, to]` has no endpoint
+        // inside the window, but still overlaps it.
+        assert!(interval_overlaps_window(Some(5), Some(25), (10, 20)));
+    }
+
+    #[test]
+    fn interval_entirely_before_window_does_not_match() {
+        assert!(!interval_overlaps_window(Some(0), Some(5), (10, 20)));
+    }
+
+    #[test]
+    fn interval_entirely_after_window_does_not_match() {
+        assert!(!interval_overlaps_window(Some(25), Some(30), (10, 20)));
+    }
+
+    #[test]
+    fn still_open_interval_overlaps_if_it_entered_before_window_end() {
+        assert!(interval_overlaps_window(Some(15), None, (10, 20)));
+        assert!(!interval_overlaps_window(Some(25), None, (10, 20)));
+    }
+
+    #[test]
+    fn interval_missing_entered_overlaps_if_it_exited_after_window_start() {
+        assert!(interval_overlaps_window(None, Some(15), (10, 20)));
+        assert!(!interval_overlaps_window(None, Some(5), (10, 20)));
+    }
 }
 
 /// ## UI memebers:
 impl SpanTree {
     pub fn tree_ui(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Download Chrome trace…").clicked() {
+                download_text(
+                    "trace.json",
+                    "application/json",
+                    &self.to_chrome_trace(),
+                );
+            }
+            if ui.button("Download Graphviz DOT…").clicked() {
+                download_text("spans.dot", "text/vnd.graphviz", &self.to_dot());
+            }
+        });
+
+        let filter_id = Self::filter_id();
+        let mut filter = ui.data_mut(|data| {
+            data.get_temp_mut_or_insert_with(filter_id, SpanFilter::default)
+                .clone()
+        });
+        let filter_before = filter.clone();
+        self.filter_ui(ui, &mut filter);
+        let filter_changed = filter != filter_before;
+        ui.data_mut(|data| data.insert_temp(filter_id, filter.clone()));
+
+        let matches_id = egui::Id::new("span_filter_matches");
+        let generation_id = egui::Id::new("span_filter_matches_generation");
+        let matching = filter.is_active().then(|| {
+            ui.data_mut(|data| {
+                // Invalidate the cache whenever the filter changes *or* new spans/events have
+                // arrived since it was computed — otherwise a live stream with a fixed filter
+                // would never show newly-matching spans.
+                let data_changed = data.get_temp::<u64>(generation_id) != Some(self.generation);
+                if !filter_changed && !data_changed {
+                    if let Some(cached) = data.get_temp::<HashSet<SpanId>>(matches_id) {
+                        return cached;
+                    }
+                }
+                let computed = self.matching_spans(&filter);
+                data.insert_temp(matches_id, computed.clone());
+                data.insert_temp(generation_id, self.generation);
+                computed
+            })
+        });
+
+        // If `timeline_ui` just selected a span, expand the tree down to it.
+        let jump_to_path = ui.data_mut(|data| {
+            data.remove::<SpanId>(Self::selected_span_id())
+                .map(|span_id| self.path_to_root(&span_id))
+        });
+
         egui::ScrollArea::vertical()
             .auto_shrink([false; 2])
             .show(ui, |ui| {
                 for span_id in &self.roots {
-                    self.tree_node_ui(ui, 0, span_id);
+                    self.tree_node_ui(ui, 0, span_id, matching.as_ref(), jump_to_path.as_ref());
                 }
 
-                if !self.orphan_events.is_empty() {
+                let visible_orphans: Vec<_> = self
+                    .orphan_events
+                    .iter()
+                    .filter(|(_, event)| {
+                        matching.is_none() || self.orphan_event_matches(event, &filter)
+                    })
+                    .collect();
+                if !visible_orphans.is_empty() {
                     ui.separator();
                     ui.label("Events outside of any span:");
                     ui.indent("events", |ui| {
-                        for (time, event) in &self.orphan_events {
+                        for (time, event) in visible_orphans {
                             self.ui_timed_data_event(ui, time, event);
                         }
                     });
@@ -205,24 +976,120 @@ impl SpanTree {
             });
     }
 
-    fn tree_node_ui(&self, ui: &mut egui::Ui, depth: usize, span_id: &SpanId) {
+    /// The search/filter bar shown above the tree. Returns nothing; mutates `filter` in place.
+    fn filter_ui(&self, ui: &mut egui::Ui, filter: &mut SpanFilter) {
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut filter.name_query)
+                .on_hover_text("Match against span name or source location");
+
+            ui.label("field");
+            ui.add(egui::TextEdit::singleline(&mut filter.field_key).desired_width(80.0));
+            ui.label("=");
+            ui.add(egui::TextEdit::singleline(&mut filter.field_value).desired_width(80.0));
+
+            let mut levels: Vec<rr_data::Level> = self
+                .callsites
+                .values()
+                .map(|callsite| callsite.level.clone())
+                .collect();
+            levels.sort_by_key(|level| level.to_string());
+            levels.dedup();
+
+            for level in levels {
+                let mut enabled = filter
+                    .levels
+                    .as_ref()
+                    .map_or(true, |levels| levels.contains(&level));
+                if ui.checkbox(&mut enabled, level.to_string()).changed() {
+                    let mut selected = filter.levels.clone().unwrap_or_else(|| {
+                        self.callsites
+                            .values()
+                            .map(|callsite| callsite.level.clone())
+                            .collect()
+                    });
+                    if enabled {
+                        if !selected.contains(&level) {
+                            selected.push(level);
+                        }
+                    } else {
+                        selected.retain(|l| *l != level);
+                    }
+                    filter.levels = Some(selected);
+                }
+            }
+
+            if ui.button("Clear filter").clicked() {
+                *filter = SpanFilter::default();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Time window:");
+            let mut window = filter
+                .time_window
+                .map_or((0.0, 0.0), |(from, to)| (time_to_micros(from), time_to_micros(to)));
+
+            ui.label("from");
+            let from_changed = ui
+                .add(egui::DragValue::new(&mut window.0).suffix("µs"))
+                .changed();
+            ui.label("to");
+            let to_changed = ui
+                .add(egui::DragValue::new(&mut window.1).suffix("µs"))
+                .changed();
+
+            if from_changed || to_changed {
+                filter.time_window = Some((micros_to_time(window.0), micros_to_time(window.1)));
+            }
+
+            if filter.time_window.is_some() && ui.button("Clear time window").clicked() {
+                filter.time_window = None;
+            }
+        });
+    }
+
+    fn tree_node_ui(
+        &self,
+        ui: &mut egui::Ui,
+        depth: usize,
+        span_id: &SpanId,
+        matching: Option<&HashSet<SpanId>>,
+        jump_to_path: Option<&HashSet<SpanId>>,
+    ) {
+        if let Some(matching) = matching {
+            if !matching.contains(span_id) {
+                return;
+            }
+        }
+
         if let Some(node) = self.nodes.get(span_id) {
             let name = self.span_name(span_id);
-            egui::CollapsingHeader::new(name)
+            let mut header = egui::CollapsingHeader::new(name)
                 .id_source(span_id)
-                .default_open(depth < 4)
-                .show(ui, |ui| {
-                    self.tree_node_ui_impl(ui, depth, node);
-                });
+                .default_open(depth < 4);
+            if jump_to_path.is_some_and(|path| path.contains(span_id)) {
+                header = header.open(Some(true));
+            }
+            header.show(ui, |ui| {
+                self.tree_node_ui_impl(ui, depth, node, matching, jump_to_path);
+            });
         } else {
             ui.colored_label(ERROR_COLOR, "Missing span");
         }
     }
 
-    fn tree_node_ui_impl(&self, ui: &mut egui::Ui, depth: usize, node: &SpanNode) {
+    fn tree_node_ui_impl(
+        &self,
+        ui: &mut egui::Ui,
+        depth: usize,
+        node: &SpanNode,
+        matching: Option<&HashSet<SpanId>>,
+        jump_to_path: Option<&HashSet<SpanId>>,
+    ) {
         self.ui_span_summary(ui, node);
         for child in &node.children {
-            self.tree_node_ui(ui, depth + 1, child);
+            self.tree_node_ui(ui, depth + 1, child, matching, jump_to_path);
         }
     }
 
@@ -373,4 +1240,200 @@ impl SpanTree {
                 ui.end_row();
             });
     }
+}
+
+/// Horizontal pan/zoom state of the timeline, in microseconds, persisted across frames.
+#[derive(Debug, Clone, Copy)]
+struct TimelineView {
+    /// `[left, right]` of the visible time range, in microseconds.
+    range: (f64, f64),
+}
+
+const TIMELINE_ROW_HEIGHT: f32 = 18.0;
+
+/// ## Timeline
+impl SpanTree {
+    /// Draw spans as horizontal bars on a shared time axis (depth on the Y axis), with
+    /// pan/zoom and click-to-select. The visual counterpart to [`Self::to_chrome_trace`].
+    pub fn timeline_ui(&self, ui: &mut egui::Ui) {
+        let Some((min_time, max_time)) = self.time_bounds() else {
+            ui.weak("Nothing recorded yet.");
+            return;
+        };
+        let now_micros = time_to_micros(max_time);
+
+        let view_id = ui.id().with("timeline_view");
+        let mut view = ui.data_mut(|data| {
+            *data.get_temp_mut_or_insert_with(view_id, || TimelineView {
+                range: (time_to_micros(min_time), now_micros),
+            })
+        });
+
+        let desired_size = egui::vec2(ui.available_width(), ui.available_height().max(200.0));
+        let (rect, response) =
+            ui.allocate_exact_size(desired_size, egui::Sense::click_and_drag());
+
+        if rect.width() > 0.0 {
+            let micros_per_point = (view.range.1 - view.range.0) / rect.width() as f64;
+
+            if response.dragged() {
+                let delta_micros = -response.drag_delta().x as f64 * micros_per_point;
+                view.range.0 += delta_micros;
+                view.range.1 += delta_micros;
+            }
+
+            if let Some(hover_pos) = response.hover_pos() {
+                let scroll_delta = ui.input(|i| i.scroll_delta.y);
+                if scroll_delta != 0.0 {
+                    let zoom = (1.0 - scroll_delta as f64 * 0.001).clamp(0.1, 10.0);
+                    let hover_micros = view.range.0 + (hover_pos.x - rect.left()) as f64 * micros_per_point;
+                    view.range.0 = hover_micros - (hover_micros - view.range.0) * zoom;
+                    view.range.1 = hover_micros + (view.range.1 - hover_micros) * zoom;
+                }
+            }
+        }
+
+        let micros_per_point = ((view.range.1 - view.range.0) / rect.width().max(1.0) as f64).max(f64::EPSILON);
+        let time_to_x = |micros: f64| -> f32 {
+            rect.left() + ((micros - view.range.0) / micros_per_point) as f32
+        };
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        for (span_id, node) in &self.nodes {
+            let depth = self.span_depth(span_id);
+            let y_top = rect.top() + depth as f32 * TIMELINE_ROW_HEIGHT;
+            if y_top > rect.bottom() {
+                continue;
+            }
+            let y_bottom = (y_top + TIMELINE_ROW_HEIGHT - 2.0).min(rect.bottom());
+
+            let callsite = self.callsites.get(&node.span.callsite_id);
+            let color = callsite.map_or(Color32::GRAY, |c| timeline_level_color(&c.level.to_string()));
+
+            for (interval_index, interval) in node.intervals.iter().enumerate() {
+                let Some(entered) = interval.entered else {
+                    continue;
+                };
+                let start_micros = time_to_micros(entered);
+                let end_micros = interval.exited.map_or(now_micros, time_to_micros);
+
+                let x0 = time_to_x(start_micros).max(rect.left());
+                let x1 = time_to_x(end_micros).min(rect.right());
+                if x1 < rect.left() || x0 > rect.right() || x1 <= x0 {
+                    continue;
+                }
+
+                let bar_rect = egui::Rect::from_min_max(
+                    egui::pos2(x0, y_top),
+                    egui::pos2(x1, y_bottom),
+                );
+                let bar_id = ui.id().with((span_id, "interval", interval_index));
+                let bar_response = ui.interact(bar_rect, bar_id, egui::Sense::click());
+
+                painter.rect_filled(bar_rect, 1.0, color);
+
+                bar_response
+                    .clone()
+                    .on_hover_ui(|ui| self.span_summary_ui_by_id(ui, span_id));
+
+                if bar_response.clicked() {
+                    ui.data_mut(|data| data.insert_temp(Self::selected_span_id(), *span_id));
+                }
+            }
+
+            for (time, _event) in &node.events {
+                let x = time_to_x(time_to_micros(*time));
+                if x < rect.left() || x > rect.right() {
+                    continue;
+                }
+                painter.circle_filled(egui::pos2(x, (y_top + y_bottom) * 0.5), 2.5, Color32::WHITE);
+            }
+        }
+
+        ui.data_mut(|data| data.insert_temp(view_id, view));
+    }
+
+    fn time_bounds(&self) -> Option<(Time, Time)> {
+        let mut bounds: Option<(Time, Time)> = None;
+        let mut include = |time: Time| {
+            bounds = Some(match bounds {
+                Some((min, max)) => (if time < min { time } else { min }, if time > max { time } else { max }),
+                None => (time, time),
+            });
+        };
+
+        for node in self.nodes.values() {
+            for interval in &node.intervals {
+                if let Some(t) = interval.entered {
+                    include(t);
+                }
+                if let Some(t) = interval.exited {
+                    include(t);
+                }
+            }
+            for (t, _) in &node.events {
+                include(*t);
+            }
+        }
+        for (t, _) in &self.orphan_events {
+            include(*t);
+        }
+
+        bounds
+    }
+
+    /// Depth of `span_id` in its tree, counting steps up the parent chain (root = 0).
+    fn span_depth(&self, span_id: &SpanId) -> usize {
+        let mut depth = 0;
+        let mut current = *span_id;
+        while let Some(parent) = self
+            .nodes
+            .get(&current)
+            .and_then(|node| node.span.parent_span_id)
+        {
+            depth += 1;
+            current = parent;
+        }
+        depth
+    }
+
+    /// The egui memory key `tree_ui` polls to find a span that `timeline_ui` just selected.
+    fn selected_span_id() -> egui::Id {
+        egui::Id::new("span_tree_selected_span")
+    }
+
+    /// The egui memory key holding the current [`SpanFilter`], as set by the filter bar in
+    /// [`Self::filter_ui`].
+    fn filter_id() -> egui::Id {
+        egui::Id::new("span_filter")
+    }
+
+    /// `span_id` and all of its ancestors, so the tree view can be force-expanded down to it.
+    fn path_to_root(&self, span_id: &SpanId) -> HashSet<SpanId> {
+        let mut path = HashSet::new();
+        let mut current = *span_id;
+        path.insert(current);
+        while let Some(parent) = self
+            .nodes
+            .get(&current)
+            .and_then(|node| node.span.parent_span_id)
+        {
+            path.insert(parent);
+            current = parent;
+        }
+        path
+    }
+}
+
+fn timeline_level_color(level: &str) -> Color32 {
+    match level.to_lowercase().as_str() {
+        "error" => Color32::RED,
+        "warn" | "warning" => Color32::from_rgb(255, 165, 0),
+        "info" => Color32::from_rgb(0, 150, 0),
+        "debug" => Color32::from_rgb(60, 120, 255),
+        "trace" => Color32::GRAY,
+        _ => Color32::DARK_GRAY,
+    }
 }
\ No newline at end of file